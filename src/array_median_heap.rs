@@ -0,0 +1,404 @@
+use core::cmp::Ordering::*;
+use core::fmt::{Debug, Formatter, Result};
+
+use crate::Median;
+
+/// A fixed-capacity, allocation-free store of up to `N` elements split into two sorted runs
+/// that grow toward each other from opposite ends of *one shared* array: `left` occupies
+/// `items[..left_len]` ascending (its last slot holds the largest element of the lower half),
+/// `right` occupies `items[N - right_len..]` ascending (its first slot holds the smallest
+/// element of the upper half). Giving each half its own `N`-sized array would let
+/// [`ArrayMedianHeap`] use up to twice the memory it actually needs, since `left_len +
+/// right_len` never exceeds `N`.
+struct SplitArray<T, const N: usize> {
+  items: [Option<T>; N],
+  left_len: usize,
+  right_len: usize,
+}
+
+impl<T, const N: usize> Default for SplitArray<T, N> {
+  fn default() -> Self {
+    Self { items: core::array::from_fn(|_| None), left_len: 0, right_len: 0 }
+  }
+}
+
+impl<T: Ord, const N: usize> SplitArray<T, N> {
+  fn left_len(&self) -> usize {
+    self.left_len
+  }
+
+  fn right_len(&self) -> usize {
+    self.right_len
+  }
+
+  fn left_max(&self) -> Option<&T> {
+    self.left_len.checked_sub(1).and_then(|i| self.items[i].as_ref())
+  }
+
+  fn right_min(&self) -> Option<&T> {
+    (self.right_len > 0).then(|| self.items[N - self.right_len].as_ref().unwrap())
+  }
+
+  /// Inserts `value` into the left run, keeping it sorted ascending.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the combined runs are already at capacity `N`.
+  fn push_left(&mut self, value: T) {
+    assert!(self.left_len + self.right_len < N, "SplitArray is at capacity");
+
+    let mut i = self.left_len;
+    while i > 0 && self.items[i - 1].as_ref().unwrap() > &value {
+      self.items.swap(i, i - 1);
+      i -= 1;
+    }
+
+    self.items[i] = Some(value);
+    self.left_len += 1;
+  }
+
+  /// Inserts `value` into the right run, keeping it sorted ascending.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the combined runs are already at capacity `N`.
+  fn push_right(&mut self, value: T) {
+    assert!(self.left_len + self.right_len < N, "SplitArray is at capacity");
+
+    let mut i = N - self.right_len - 1;
+    while i + 1 < N && self.items[i + 1].as_ref().is_some_and(|v| v < &value) {
+      self.items.swap(i, i + 1);
+      i += 1;
+    }
+
+    self.items[i] = Some(value);
+    self.right_len += 1;
+  }
+
+  /// Removes and returns the largest element of the left run, i.e. the left/right boundary.
+  fn pop_left_max(&mut self) -> Option<T> {
+    let i = self.left_len.checked_sub(1)?;
+    self.left_len -= 1;
+    self.items[i].take()
+  }
+
+  /// Removes and returns the smallest element of the left run, shifting the rest down.
+  fn pop_left_min(&mut self) -> Option<T> {
+    if self.left_len == 0 {
+      return None;
+    }
+
+    let value = self.items[0].take();
+    self.items[..self.left_len].rotate_left(1);
+    self.left_len -= 1;
+
+    value
+  }
+
+  /// Removes and returns the smallest element of the right run, i.e. the left/right boundary.
+  ///
+  /// Unlike [`Self::pop_left_min`], this needs no shift: the right run is anchored at `N`, so
+  /// its remaining elements are already at the indices the shrunk `right_len` expects.
+  fn pop_right_min(&mut self) -> Option<T> {
+    if self.right_len == 0 {
+      return None;
+    }
+
+    let start = N - self.right_len;
+    let value = self.items[start].take();
+    self.right_len -= 1;
+
+    value
+  }
+
+  /// Removes and returns the largest element of the right run, shifting the rest up so the run
+  /// stays anchored at `N`.
+  fn pop_right_max(&mut self) -> Option<T> {
+    if self.right_len == 0 {
+      return None;
+    }
+
+    let start = N - self.right_len;
+    let value = self.items[N - 1].take();
+    self.items[start..].rotate_right(1);
+    self.right_len -= 1;
+
+    value
+  }
+}
+
+/// Formats the live elements of a `[..len]` or `[N - len..]` slice of a [`SplitArray`] as a
+/// list, without collecting them into a heap-allocated buffer first.
+struct DebugRun<'a, T>(&'a [Option<T>]);
+
+impl<T: Debug> Debug for DebugRun<'_, T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.debug_list().entries(self.0.iter().map(|item| item.as_ref().unwrap())).finish()
+  }
+}
+
+/// A [`crate::MedianHeap`] variant with a compile-time capacity `N`, backed by one inline array
+/// instead of `Vec`-based heaps, so it never allocates and can run on embedded targets without a
+/// global allocator.
+///
+/// Once `N` elements have been pushed, further pushes evict an element exactly the way
+/// [`crate::MedianHeap::push`] does for a heap with `max_size` set: the smallest item is
+/// dropped if the new item is greater than the current median, the largest item is dropped if
+/// it is smaller, and both are dropped if it is equal.
+pub struct ArrayMedianHeap<T: Ord, const N: usize> {
+  items: SplitArray<T, N>,
+}
+
+impl<T: Ord, const N: usize> Default for ArrayMedianHeap<T, N> {
+  /// Creates an empty `ArrayMedianHeap<T, N>`.
+  #[inline]
+  fn default() -> Self {
+    Self { items: Default::default() }
+  }
+}
+
+impl<T: Ord + Debug, const N: usize> Debug for ArrayMedianHeap<T, N> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.debug_struct("ArrayMedianHeap")
+      .field("left", &DebugRun(&self.items.items[..self.items.left_len]))
+      .field("right", &DebugRun(&self.items.items[N - self.items.right_len..]))
+      .finish()
+  }
+}
+
+impl<T: Ord, const N: usize> ArrayMedianHeap<T, N> {
+  /// Creates an empty `ArrayMedianHeap<T, N>`.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::ArrayMedianHeap;
+  /// #
+  /// let mut heap = ArrayMedianHeap::<i32, 42>::new();
+  /// heap.push(4);
+  /// ```
+  #[inline]
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Returns the maximum number of elements the heap can hold, i.e. `N`.
+  #[inline]
+  pub fn max_size(&self) -> usize {
+    N
+  }
+
+  /// Returns the number of elements currently on the heap.
+  pub fn len(&self) -> usize {
+    self.items.left_len() + self.items.right_len()
+  }
+
+  /// Returns `true` if there are no elements on the heap.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  fn is_full(&self) -> bool {
+    self.len() >= N
+  }
+
+  /// This either returns
+  ///   - `Some(T)` containing the median value if there are an odd number of elements
+  ///   - `Some(T)` containing the two middlemost values if there are an even number of elements
+  ///   - `None` if the heap is empty
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::{ArrayMedianHeap, Median};
+  /// #
+  /// let mut heap = ArrayMedianHeap::<i32, 42>::new();
+  ///
+  /// assert_eq!(heap.median(), None);
+  ///
+  /// heap.push(1);
+  /// assert_eq!(heap.median(), Some(Median::Single(&1)));
+  ///
+  /// heap.push(3);
+  /// assert_eq!(heap.median(), Some(Median::Pair(&1, &3)));
+  /// ```
+  pub fn median(&self) -> Option<Median<&T>> {
+    match self.items.left_len().cmp(&self.items.right_len()) {
+      Less => self.items.right_min().map(Median::Single),
+      Greater => self.items.left_max().map(Median::Single),
+      Equal => self.items.left_max().and_then(|left| self.items.right_min().map(|right| Median::Pair(left, right))),
+    }
+  }
+
+  /// Pushes an item onto the median heap.
+  ///
+  /// Once the heap holds `N` elements, this evicts
+  ///   - the smallest item, if the pushed item is greater than `>` the current median
+  ///   - the largest item, if the pushed item is less than `<` the current median
+  ///   - both the smallest and the largest item, if the pushed item is equal `==` to the current median
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::ArrayMedianHeap;
+  /// #
+  /// let mut heap = ArrayMedianHeap::<i32, 2>::new();
+  ///
+  /// heap.push(1);
+  /// heap.push(1);
+  /// assert_eq!(heap.len(), 2);
+  ///
+  /// heap.push(1);
+  /// assert_eq!(heap.len(), 1);
+  /// ```
+  pub fn push(&mut self, item: T) {
+    let ordering = self
+      .median()
+      .map(|median| match median {
+        Median::Single(v) => item.cmp(v),
+        Median::Pair(v1, v2) => {
+          if item >= *v1 && item <= *v2 {
+            Equal
+          } else if item < *v1 {
+            Less
+          } else if item > *v2 {
+            Greater
+          } else {
+            Equal
+          }
+        },
+      })
+      .unwrap_or(Equal);
+
+    match ordering {
+      // The shared array has no spare slot once full, so the evicted element has to be popped
+      // before `item` is pushed, unlike the non-full branch below which can afford to push first
+      // and rebalance after. Whichever side is empty has nothing to evict from, so evict from
+      // the other side instead (only possible at `N == 1`, where one side is always empty).
+      Less if self.is_full() => {
+        if self.items.right_len() > 0 {
+          self.items.pop_right_max();
+        } else {
+          self.items.pop_left_min();
+        }
+
+        self.items.push_left(item);
+
+        if self.items.left_len() > self.items.right_len() + 1 {
+          let max = self.items.pop_left_max().unwrap();
+          self.items.push_right(max);
+        }
+      },
+      Less => {
+        self.items.push_left(item);
+
+        if self.items.left_len() > self.items.right_len() + 1 {
+          let max = self.items.pop_left_max().unwrap();
+          self.items.push_right(max);
+        }
+      },
+      Greater if self.is_full() => {
+        if self.items.left_len() > 0 {
+          self.items.pop_left_min();
+        } else {
+          self.items.pop_right_min();
+        }
+
+        self.items.push_right(item);
+
+        if self.items.right_len() > self.items.left_len() + 1 {
+          let min = self.items.pop_right_min().unwrap();
+          self.items.push_left(min);
+        }
+      },
+      Greater => {
+        self.items.push_right(item);
+
+        if self.items.right_len() > self.items.left_len() + 1 {
+          let min = self.items.pop_right_min().unwrap();
+          self.items.push_left(min);
+        }
+      },
+      Equal => {
+        if self.is_full() {
+          self.items.pop_left_min();
+          self.items.pop_right_max();
+        }
+
+        if self.items.left_len() > self.items.right_len() {
+          self.items.push_right(item);
+        } else {
+          self.items.push_left(item);
+        }
+      },
+    };
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn push() {
+    let mut heap = ArrayMedianHeap::<i32, 8>::new();
+
+    heap.push(1);
+    assert_eq!(heap.median(), Some(Median::Single(&1)));
+
+    heap.push(2);
+    assert_eq!(heap.median(), Some(Median::Pair(&1, &2)));
+
+    heap.push(3);
+    assert_eq!(heap.median(), Some(Median::Single(&2)));
+
+    heap.push(4);
+    assert_eq!(heap.median(), Some(Median::Pair(&2, &3)));
+
+    heap.push(5);
+    assert_eq!(heap.median(), Some(Median::Single(&3)));
+  }
+
+  #[test]
+  fn max_size() {
+    let mut heap = ArrayMedianHeap::<i32, 8>::new();
+
+    for i in 0..100 {
+      heap.push(i);
+
+      if i < 8 {
+        assert_eq!(heap.len(), (i + 1) as usize);
+      } else {
+        assert_eq!(heap.len(), 8);
+      }
+    }
+
+    assert_eq!(heap.median(), Some(Median::Pair(&95, &96)));
+  }
+
+  #[test]
+  fn max_size_1() {
+    let mut heap = ArrayMedianHeap::<i32, 1>::new();
+
+    heap.push(1);
+    assert_eq!(heap.median(), Some(Median::Single(&1)));
+    heap.push(2);
+    assert_eq!(heap.median(), Some(Median::Single(&2)));
+    heap.push(1);
+    assert_eq!(heap.median(), Some(Median::Single(&1)));
+  }
+
+  #[test]
+  fn size_is_tight() {
+    // Giving each half its own full-`N` array (the design this one replaced) costs twice this.
+    assert!(
+      core::mem::size_of::<ArrayMedianHeap<i64, 100>>() < 2 * core::mem::size_of::<[Option<i64>; 100]>()
+    );
+  }
+}
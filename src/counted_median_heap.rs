@@ -0,0 +1,218 @@
+use alloc::collections::BTreeMap;
+use core::fmt::{Debug, Formatter, Result};
+
+use crate::AverageWith;
+
+/// A [`crate::MedianHeap`] variant that stores distinct values together with an occurrence
+/// count instead of one slot per pushed element, so memory use is `O(D)` in the number of
+/// distinct values `D` rather than `O(n)` in the number of elements pushed.
+///
+/// `low` holds the lower half of the distribution keyed ascending (its last key is the low
+/// boundary), `high` holds the upper half keyed ascending (its first key is the high boundary),
+/// and `low_total`/`high_total` track the summed counts on each side so the two sides can be
+/// kept within one count of each other without re-counting every entry.
+pub struct CountedMedianHeap<T: Ord> {
+  low: BTreeMap<T, usize>,
+  high: BTreeMap<T, usize>,
+  low_total: usize,
+  high_total: usize,
+}
+
+impl<T: Ord> Default for CountedMedianHeap<T> {
+  /// Creates an empty `CountedMedianHeap`.
+  #[inline]
+  fn default() -> Self {
+    Self { low: BTreeMap::new(), high: BTreeMap::new(), low_total: 0, high_total: 0 }
+  }
+}
+
+impl<T: Ord + Debug> Debug for CountedMedianHeap<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.debug_struct("CountedMedianHeap").field("low", &self.low).field("high", &self.high).finish()
+  }
+}
+
+impl<T: Ord + Clone> CountedMedianHeap<T> {
+  /// Creates an empty `CountedMedianHeap`.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::CountedMedianHeap;
+  /// #
+  /// let mut heap = CountedMedianHeap::new();
+  /// heap.push(4);
+  /// ```
+  #[inline]
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Returns the number of elements pushed onto the heap, counting repeated values multiple
+  /// times.
+  pub fn len(&self) -> usize {
+    self.low_total + self.high_total
+  }
+
+  /// Returns `true` if there are no elements on the heap.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Pushes an item onto the heap, incrementing its occurrence count.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::CountedMedianHeap;
+  /// #
+  /// let mut heap = CountedMedianHeap::new();
+  ///
+  /// heap.push(1);
+  /// heap.push(1);
+  /// heap.push(2);
+  ///
+  /// assert_eq!(heap.len(), 3);
+  /// ```
+  pub fn push(&mut self, item: T) {
+    let goes_low = match (self.low.last_key_value(), self.high.first_key_value()) {
+      (Some((l, _)), _) if item <= *l => true,
+      (_, Some((h, _))) if item >= *h => false,
+      (Some(_), Some(_)) => self.low_total <= self.high_total,
+      (Some(_), None) => true,
+      (None, Some(_)) => false,
+      (None, None) => true,
+    };
+
+    if goes_low {
+      *self.low.entry(item).or_insert(0) += 1;
+      self.low_total += 1;
+    } else {
+      *self.high.entry(item).or_insert(0) += 1;
+      self.high_total += 1;
+    }
+
+    self.rebalance();
+  }
+
+  /// Moves count-weight (not whole distinct values) across the low/high boundary until the two
+  /// sides differ in summed count by at most one.
+  fn rebalance(&mut self) {
+    while self.low_total > self.high_total + 1 {
+      self.shift_low_to_high();
+    }
+
+    while self.high_total > self.low_total {
+      self.shift_high_to_low();
+    }
+  }
+
+  fn shift_low_to_high(&mut self) {
+    let mut entry = self.low.last_entry().unwrap();
+    let key = entry.key().clone();
+
+    *entry.get_mut() -= 1;
+    if *entry.get() == 0 {
+      entry.remove();
+    }
+
+    self.low_total -= 1;
+    *self.high.entry(key).or_insert(0) += 1;
+    self.high_total += 1;
+  }
+
+  fn shift_high_to_low(&mut self) {
+    let mut entry = self.high.first_entry().unwrap();
+    let key = entry.key().clone();
+
+    *entry.get_mut() -= 1;
+    if *entry.get() == 0 {
+      entry.remove();
+    }
+
+    self.high_total -= 1;
+    *self.low.entry(key).or_insert(0) += 1;
+    self.low_total += 1;
+  }
+}
+
+impl<T: Ord + AverageWith + Clone> CountedMedianHeap<T> {
+  /// Returns the running median, or `None` if the heap is empty.
+  ///
+  /// If an even number of elements have been pushed, this returns the arithmetic mean of the
+  /// two boundary values via [`AverageWith`]; otherwise it returns the single boundary value.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::CountedMedianHeap;
+  /// #
+  /// let mut heap = CountedMedianHeap::new();
+  ///
+  /// heap.push(1);
+  /// assert_eq!(heap.median(), Some(1));
+  ///
+  /// heap.push(3);
+  /// assert_eq!(heap.median(), Some(2));
+  /// ```
+  pub fn median(&self) -> Option<T> {
+    match (self.low.last_key_value(), self.high.first_key_value()) {
+      (Some((low, _)), Some((high, _))) if self.low_total == self.high_total => Some(low.average_with(high)),
+      (Some((low, _)), _) => Some(low.clone()),
+      (None, Some((high, _))) => Some(high.clone()),
+      (None, None) => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn push() {
+    let mut heap = CountedMedianHeap::<i32>::new();
+
+    heap.push(1);
+    assert_eq!(heap.median(), Some(1));
+
+    heap.push(2);
+    assert_eq!(heap.median(), Some(1));
+
+    heap.push(3);
+    assert_eq!(heap.median(), Some(2));
+
+    heap.push(4);
+    assert_eq!(heap.median(), Some(2));
+  }
+
+  #[test]
+  fn duplicates() {
+    let mut heap = CountedMedianHeap::<i32>::new();
+
+    for _ in 0..4 {
+      heap.push(1);
+    }
+
+    for _ in 0..4 {
+      heap.push(2);
+    }
+
+    assert_eq!(heap.len(), 8);
+    assert_eq!(heap.median(), Some(1));
+  }
+
+  #[test]
+  fn empty() {
+    let heap = CountedMedianHeap::<i32>::new();
+
+    assert!(heap.is_empty());
+    assert_eq!(heap.median(), None);
+  }
+}
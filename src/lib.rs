@@ -1,13 +1,45 @@
 #![deny(bad_style, future_incompatible, missing_docs, missing_debug_implementations, rust_2018_idioms)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! A median heap for keeping track of a running median.
+//!
+//! Without the default `std` feature, this crate builds `no_std` against `alloc`, but that only
+//! benefits the backends that don't depend on an external `std`-only collection: [`ArrayMedianHeap`]
+//! (no heap allocation at all) and [`CountedMedianHeap`] (backed by `alloc`'s `BTreeMap`) run on
+//! embedded targets this way. The core [`MedianHeap`], [`QuantileHeap`] and [`WeightedMedianHeap`]
+//! are backed by [`min_max_heap::MinMaxHeap`], which itself requires `std` with no `alloc`-only
+//! mode, so those three still need the `std` feature regardless; so does [`WindowedMedianHeap`],
+//! whose sliding window is indexed with a `HashMap`. Swapping `MinMaxHeap` for a pluggable
+//! `cc-traits`-style trait (so e.g. a stack-only `smallvec` could back a small window) is left for
+//! a follow-up; this pass only draws the `std`/`alloc` line where the current dependencies allow.
 
-use std::{
-  cmp::Ordering::*,
+extern crate alloc;
+
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::{
+  cmp::Ordering::{self, *},
   fmt::{Debug, Formatter, Result},
 };
 
 use min_max_heap::MinMaxHeap;
 
+mod array_median_heap;
+mod average_with;
+mod counted_median_heap;
+mod min;
+mod quantile_heap;
+mod weighted_median_heap;
+#[cfg(feature = "std")]
+mod windowed_median_heap;
+
+pub use array_median_heap::ArrayMedianHeap;
+pub use average_with::AverageWith;
+pub use counted_median_heap::CountedMedianHeap;
+pub use quantile_heap::QuantileHeap;
+pub use weighted_median_heap::WeightedMedianHeap;
+#[cfg(feature = "std")]
+pub use windowed_median_heap::WindowedMedianHeap;
+
 /// A median, consisting of either a single or a pair of values.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Median<T> {
@@ -17,15 +49,85 @@ pub enum Median<T> {
   Pair(T, T),
 }
 
-/// A median heap implemented with two binary heaps.
+/// An entry paired with the key it is ordered by.
+///
+/// Only the key takes part in comparisons, so `T` itself never needs to implement `Ord`.
 #[derive(Clone)]
-pub struct MedianHeap<T: Ord> {
+struct Keyed<K, T> {
+  key: K,
+  value: T,
+}
+
+impl<K: PartialEq, T> PartialEq for Keyed<K, T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.key == other.key
+  }
+}
+
+impl<K: Eq, T> Eq for Keyed<K, T> {}
+
+impl<K: PartialOrd, T> PartialOrd for Keyed<K, T> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    self.key.partial_cmp(&other.key)
+  }
+}
+
+impl<K: Ord, T> Ord for Keyed<K, T> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.key.cmp(&other.key)
+  }
+}
+
+impl<K: Debug, T> Debug for Keyed<K, T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    Debug::fmt(&self.key, f)
+  }
+}
+
+/// Converts `value` to `f64`, if `T` is one of the built-in numeric primitives that implement
+/// `Copy + Into<f64>` (`f32`, `f64`, `i8`, `i16`, `i32`, `u8`, `u16`, `u32`).
+///
+/// This is checked at runtime via [`core::any::Any`] rather than with a `T: Copy + Into<f64>`
+/// bound, because [`MedianHeap::push`]/`pop_min`/`pop_max`/`pop_median` are generic over every
+/// `T`, including non-numeric ones, and stable Rust has no way to conditionally call a trait
+/// method inside a function generic over `T` based on a bound that isn't part of that function's
+/// own signature (that would require the unstable `specialization` feature). The downside is
+/// that a user-defined type implementing `Copy + Into<f64>` itself is not recognized here, so its
+/// [`MedianHeap::mean`]/`sample_variance`/`error` would stay at their empty-heap values.
+fn welford_sample<T: 'static>(value: &T) -> Option<f64> {
+  let value = value as &dyn core::any::Any;
+
+  macro_rules! try_numeric {
+    ($($ty:ty),+) => {
+      $(if let Some(value) = value.downcast_ref::<$ty>() {
+        return Some((*value).into());
+      })+
+    };
+  }
+
+  try_numeric!(f32, f64, i8, i16, i32, u8, u16, u32);
+
+  None
+}
+
+/// A median heap implemented with two binary heaps.
+///
+/// Elements are ordered by a key of type `K`, extracted from each pushed `T` with a stored
+/// key function. By default `K` is `T` itself and the key function is [`Clone::clone`], which
+/// is exactly the plain `T: Ord` heap this type started out as; use [`MedianHeap::new_by_key`]
+/// or [`MedianHeap::with_max_size_by_key`] to run a median over a type that is not `Ord`, or
+/// over a projection of it (e.g. the median latency of request structs).
+pub struct MedianHeap<T, K: Ord = T> {
   max_size: Option<usize>,
-  left: MinMaxHeap<T>,
-  right: MinMaxHeap<T>,
+  key: Rc<dyn Fn(&T) -> K>,
+  left: MinMaxHeap<Keyed<K, T>>,
+  right: MinMaxHeap<Keyed<K, T>>,
+  welford_n: u64,
+  welford_avg: f64,
+  welford_v: f64,
 }
 
-impl<T: Ord> Default for MedianHeap<T> {
+impl<T: Ord + Clone + 'static> Default for MedianHeap<T> {
   /// Creates an empty `MedianHeap`.
   #[inline]
   fn default() -> Self {
@@ -33,7 +135,21 @@ impl<T: Ord> Default for MedianHeap<T> {
   }
 }
 
-impl<T: Ord + Debug> Debug for MedianHeap<T> {
+impl<T: Clone, K: Ord + Clone> Clone for MedianHeap<T, K> {
+  fn clone(&self) -> Self {
+    Self {
+      max_size: self.max_size,
+      key: Rc::clone(&self.key),
+      left: self.left.clone(),
+      right: self.right.clone(),
+      welford_n: self.welford_n,
+      welford_avg: self.welford_avg,
+      welford_v: self.welford_v,
+    }
+  }
+}
+
+impl<T: Debug, K: Ord + Debug> Debug for MedianHeap<T, K> {
   fn fmt(&self, f: &mut Formatter<'_>) -> Result {
     let mut s = f.debug_struct("MedianHeap");
 
@@ -48,7 +164,7 @@ impl<T: Ord + Debug> Debug for MedianHeap<T> {
   }
 }
 
-impl<T: Ord> MedianHeap<T> {
+impl<T: Ord + Clone + 'static> MedianHeap<T> {
   /// Creates an empty `MedianHeap`.
   ///
   /// # Examples
@@ -63,7 +179,7 @@ impl<T: Ord> MedianHeap<T> {
   /// ```
   #[inline]
   pub fn new() -> Self {
-    Self { max_size: Default::default(), left: Default::default(), right: Default::default() }
+    Self::new_by_key(T::clone)
   }
 
   /// Creates an empty `MedianHeap` which can only grow to `max_size`.
@@ -84,14 +200,71 @@ impl<T: Ord> MedianHeap<T> {
   /// ```
   #[inline]
   pub fn with_max_size(max_size: usize) -> Self {
+    Self::with_max_size_by_key(max_size, T::clone)
+  }
+}
+
+impl<T, K: Ord> MedianHeap<T, K> {
+  /// Creates an empty `MedianHeap` ordered by a key extracted from each element with `key`,
+  /// rather than by `T: Ord` directly.
+  ///
+  /// This allows running a median over a type that is not `Ord`, or over a projection of one,
+  /// e.g. `MedianHeap::new_by_key(|request: &Request| request.latency)`.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new_by_key(|s: &String| s.len());
+  /// heap.push(String::from("abc"));
+  /// ```
+  #[inline]
+  pub fn new_by_key<F: Fn(&T) -> K + 'static>(key: F) -> Self {
+    Self {
+      max_size: Default::default(),
+      key: Rc::new(key),
+      left: Default::default(),
+      right: Default::default(),
+      welford_n: 0,
+      welford_avg: 0.0,
+      welford_v: 0.0,
+    }
+  }
+
+  /// Creates an empty `MedianHeap` which can only grow to `max_size`, ordered by a key
+  /// extracted from each element with `key`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `max_size` is zero.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::with_max_size_by_key(42, |s: &String| s.len());
+  /// heap.push(String::from("abc"));
+  /// ```
+  #[inline]
+  pub fn with_max_size_by_key<F: Fn(&T) -> K + 'static>(max_size: usize, key: F) -> Self {
     assert!(max_size > 0);
 
     let heap_size = (max_size + 3) / 2;
 
     Self {
       max_size: Some(max_size),
+      key: Rc::new(key),
       left: MinMaxHeap::with_capacity(heap_size),
       right: MinMaxHeap::with_capacity(heap_size),
+      welford_n: 0,
+      welford_avg: 0.0,
+      welford_v: 0.0,
     }
   }
 
@@ -156,9 +329,19 @@ impl<T: Ord> MedianHeap<T> {
     }
   }
 
+  fn median_key(&self) -> Option<Median<&K>> {
+    match self.left.len().cmp(&self.right.len()) {
+      Less => self.right.peek_min().map(|entry| Median::Single(&entry.key)),
+      Greater => self.left.peek_max().map(|entry| Median::Single(&entry.key)),
+      Equal => {
+        self.left.peek_max().and_then(|left| self.right.peek_min().map(|right| Median::Pair(&left.key, &right.key)))
+      },
+    }
+  }
+
   /// This either returns
   ///   - `Some(T)` containing the median value if there are an odd number of elements
-  ///   - `Some(T)` containing the arithmetic mean of the two middlemost values if there are an even number of elements
+  ///   - `Some(T)` containing the two middlemost values if there are an even number of elements
   ///   - `None` if the heap is empty
   ///
   /// # Examples
@@ -180,9 +363,46 @@ impl<T: Ord> MedianHeap<T> {
   /// ```
   pub fn median(&self) -> Option<Median<&T>> {
     match self.left.len().cmp(&self.right.len()) {
-      Less => self.right.peek_min().map(Median::Single),
-      Greater => self.left.peek_max().map(Median::Single),
-      Equal => self.left.peek_max().and_then(|left| self.right.peek_min().map(|right| Median::Pair(left, right))),
+      Less => self.right.peek_min().map(|entry| Median::Single(&entry.value)),
+      Greater => self.left.peek_max().map(|entry| Median::Single(&entry.value)),
+      Equal => self
+        .left
+        .peek_max()
+        .and_then(|left| self.right.peek_min().map(|right| Median::Pair(&left.value, &right.value))),
+    }
+  }
+
+  /// Folds `value` into the running Welford state as a newly observed sample, if
+  /// [`welford_sample`] recognizes `T` as numeric.
+  fn welford_push(&mut self, value: &T)
+  where
+    T: 'static,
+  {
+    if let Some(x) = welford_sample(value) {
+      self.welford_n += 1;
+      let delta = x - self.welford_avg;
+      self.welford_avg += delta / self.welford_n as f64;
+      self.welford_v += delta * (x - self.welford_avg);
+    }
+  }
+
+  /// Removes `value` from the running Welford state, reversing [`Self::welford_push`].
+  fn welford_remove(&mut self, value: &T)
+  where
+    T: 'static,
+  {
+    if let Some(x) = welford_sample(value) {
+      if self.welford_n <= 1 {
+        self.welford_n = 0;
+        self.welford_avg = 0.0;
+        self.welford_v = 0.0;
+      } else {
+        let n = self.welford_n as f64;
+        self.welford_n -= 1;
+        let avg = (self.welford_avg * n - x) / self.welford_n as f64;
+        self.welford_v -= (x - avg) * (x - self.welford_avg);
+        self.welford_avg = avg;
+      }
     }
   }
 
@@ -235,52 +455,63 @@ impl<T: Ord> MedianHeap<T> {
   /// heap.push(1);
   /// assert_eq!(heap.len(), 1);
   /// ```
-  pub fn push(&mut self, item: T) {
-    match self
-      .median()
+  pub fn push(&mut self, item: T)
+  where
+    T: 'static,
+  {
+    let key = (self.key)(&item);
+    self.welford_push(&item);
+
+    let ordering = self
+      .median_key()
       .map(|median| match median {
-        Median::Single(v) => item.cmp(v),
+        Median::Single(v) => key.cmp(v),
         Median::Pair(v1, v2) => {
-          if item >= *v1 && item <= *v2 {
+          if key >= *v1 && key <= *v2 {
             Equal
-          } else if item < *v1 {
+          } else if key < *v1 {
             Less
-          } else if item > *v2 {
+          } else if key > *v2 {
             Greater
           } else {
             Equal
           }
         },
       })
-      .unwrap_or(Equal)
-    {
+      .unwrap_or(Equal);
+
+    let entry = Keyed { key, value: item };
+
+    match ordering {
       Less if self.is_full() => {
-        self.left.push(item);
+        self.left.push(entry);
 
         if self.left.len() > self.right.len() {
           self.right.push(self.left.pop_max().unwrap());
         }
 
-        self.right.pop_max();
+        let evicted = self.right.pop_max().unwrap();
+        self.welford_remove(&evicted.value);
       },
       Less => {
-        self.left.push(item);
+        self.left.push(entry);
 
         if self.left.len() > self.right.len() + 1 {
           self.right.push(self.left.pop_max().unwrap());
         }
       },
       Greater if self.is_full() => {
-        self.right.push(item);
+        self.right.push(entry);
 
         if self.right.len() > self.left.len() {
           self.left.push(self.right.pop_min().unwrap());
         }
 
-        self.left.pop_min();
+        let evicted = self.left.pop_min().unwrap();
+        self.welford_remove(&evicted.value);
       },
       Greater => {
-        self.right.push(item);
+        self.right.push(entry);
 
         if self.right.len() > self.left.len() + 1 {
           self.left.push(self.right.pop_min().unwrap());
@@ -288,18 +519,391 @@ impl<T: Ord> MedianHeap<T> {
       },
       Equal => {
         if self.is_full() {
-          self.left.pop_min();
-          self.right.pop_max();
+          if let Some(evicted_min) = self.left.pop_min() {
+            self.welford_remove(&evicted_min.value);
+          }
+
+          if let Some(evicted_max) = self.right.pop_max() {
+            self.welford_remove(&evicted_max.value);
+          }
         }
 
         if self.left.len() > self.right.len() {
-          self.right.push(item);
+          self.right.push(entry);
         } else {
-          self.left.push(item);
+          self.left.push(entry);
         }
       },
     };
   }
+
+  /// Removes and returns the smallest item on the heap.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(2);
+  /// heap.push(1);
+  /// heap.push(3);
+  ///
+  /// assert_eq!(heap.pop_min(), Some(1));
+  /// assert_eq!(heap.len(), 2);
+  /// ```
+  pub fn pop_min(&mut self) -> Option<T>
+  where
+    T: 'static,
+  {
+    let popped = self.left.pop_min().or_else(|| self.right.pop_min())?;
+    self.rebalance();
+    self.welford_remove(&popped.value);
+    Some(popped.value)
+  }
+
+  /// Removes and returns the largest item on the heap.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(2);
+  /// heap.push(1);
+  /// heap.push(3);
+  ///
+  /// assert_eq!(heap.pop_max(), Some(3));
+  /// assert_eq!(heap.len(), 2);
+  /// ```
+  pub fn pop_max(&mut self) -> Option<T>
+  where
+    T: 'static,
+  {
+    let popped = self.right.pop_max().or_else(|| self.left.pop_max())?;
+    self.rebalance();
+    self.welford_remove(&popped.value);
+    Some(popped.value)
+  }
+
+  /// Removes and returns the median value(s), in the same shape [`MedianHeap::median`] would
+  /// have returned them.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::{MedianHeap, Median};
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(1);
+  /// heap.push(2);
+  /// heap.push(3);
+  ///
+  /// assert_eq!(heap.pop_median(), Some(Median::Single(2)));
+  /// assert_eq!(heap.len(), 2);
+  /// ```
+  pub fn pop_median(&mut self) -> Option<Median<T>>
+  where
+    T: 'static,
+  {
+    match self.left.len().cmp(&self.right.len()) {
+      Less => {
+        let entry = self.right.pop_min()?;
+        self.welford_remove(&entry.value);
+        Some(Median::Single(entry.value))
+      },
+      Greater => {
+        let entry = self.left.pop_max()?;
+        self.welford_remove(&entry.value);
+        Some(Median::Single(entry.value))
+      },
+      Equal => {
+        let left = self.left.pop_max()?;
+        let right = self.right.pop_min()?;
+        self.welford_remove(&left.value);
+        self.welford_remove(&right.value);
+        Some(Median::Pair(left.value, right.value))
+      },
+    }
+  }
+
+  /// Removes all items from the heap.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(1);
+  /// heap.clear();
+  ///
+  /// assert!(heap.is_empty());
+  /// ```
+  pub fn clear(&mut self) {
+    self.left.clear();
+    self.right.clear();
+    self.welford_n = 0;
+    self.welford_avg = 0.0;
+    self.welford_v = 0.0;
+  }
+
+  /// Moves every element of `other` into `self`, leaving `other` empty.
+  ///
+  /// Each element is re-inserted via [`MedianHeap::push`], so `self`'s key function and
+  /// `max_size` govern the merged result; `other`'s key function and `max_size` are discarded.
+  /// This lets partial medians computed over separate chunks of data be folded together.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut a = MedianHeap::new();
+  /// a.push(1);
+  /// a.push(3);
+  ///
+  /// let mut b = MedianHeap::new();
+  /// b.push(2);
+  ///
+  /// a.append(&mut b);
+  ///
+  /// assert!(b.is_empty());
+  /// assert_eq!(a.len(), 3);
+  /// ```
+  pub fn append(&mut self, other: &mut Self)
+  where
+    T: 'static,
+  {
+    while let Some(value) = other.pop_min() {
+      self.push(value);
+    }
+  }
+
+  /// Restores the invariant that `left` and `right` differ in length by at most one, after a
+  /// removal may have pushed them further apart, by migrating the boundary element across.
+  fn rebalance(&mut self) {
+    if self.left.len() > self.right.len() + 1 {
+      self.right.push(self.left.pop_max().unwrap());
+    } else if self.right.len() > self.left.len() + 1 {
+      self.left.push(self.right.pop_min().unwrap());
+    }
+  }
+
+  fn values(&self) -> impl Iterator<Item = &T> {
+    self.left.iter().chain(self.right.iter()).map(|entry| &entry.value)
+  }
+}
+
+impl<T: Copy + Into<f64>, K: Ord> MedianHeap<T, K> {
+  /// Returns the arithmetic mean of the heap's elements, or `None` if the heap is empty.
+  ///
+  /// This is maintained incrementally on every [`MedianHeap::push`]/`pop_min`/`pop_max` using
+  /// Welford's online algorithm, which avoids the numerical instability of a naive sum-of-squares,
+  /// so callers tracking a running median can get the mean of the same stream without a second
+  /// pass over its elements. Since this method is generic over any `T: Copy + Into<f64>` but the
+  /// heap only recognizes the built-in numeric primitives when folding a pushed/popped value into
+  /// that running state (stable Rust has no way to do this for an arbitrary caller-defined `T`
+  /// without the unstable `specialization` feature), this always returns `None` for a
+  /// non-primitive `T`, even one that implements `Copy + Into<f64>` itself.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(1);
+  /// heap.push(2);
+  /// heap.push(3);
+  ///
+  /// assert_eq!(heap.mean(), Some(2.0));
+  /// ```
+  pub fn mean(&self) -> Option<f64> {
+    (self.welford_n > 0).then_some(self.welford_avg)
+  }
+
+  /// Returns the sample variance of the heap's elements, or `None` if the heap has fewer than
+  /// two elements.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(2);
+  /// heap.push(4);
+  /// heap.push(4);
+  /// heap.push(4);
+  /// heap.push(5);
+  /// heap.push(5);
+  /// heap.push(7);
+  /// heap.push(9);
+  ///
+  /// assert_eq!(heap.sample_variance(), Some(4.571428571428571));
+  /// ```
+  pub fn sample_variance(&self) -> Option<f64> {
+    (self.welford_n > 1).then(|| self.welford_v / (self.welford_n - 1) as f64)
+  }
+
+  /// Returns the standard error of the mean of the heap's elements, or `None` if the heap has
+  /// fewer than two elements.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(2);
+  /// heap.push(4);
+  /// heap.push(4);
+  /// heap.push(4);
+  /// heap.push(5);
+  /// heap.push(5);
+  /// heap.push(7);
+  /// heap.push(9);
+  ///
+  /// assert_eq!(heap.error(), Some(0.7559289460184544));
+  /// ```
+  pub fn error(&self) -> Option<f64> {
+    self.sample_variance().map(|variance| (variance / self.len() as f64).sqrt())
+  }
+}
+
+impl<T: Ord> MedianHeap<T> {
+  /// Returns an iterator over the heap's elements, in arbitrary order.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(1);
+  /// heap.push(2);
+  ///
+  /// assert_eq!(heap.iter().count(), 2);
+  /// ```
+  pub fn iter(&self) -> impl Iterator<Item = &T> {
+    self.values()
+  }
+
+  /// Consumes the heap and returns a `Vec` of its elements in ascending order.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::MedianHeap;
+  /// #
+  /// let mut heap = MedianHeap::new();
+  ///
+  /// heap.push(3);
+  /// heap.push(1);
+  /// heap.push(2);
+  ///
+  /// assert_eq!(heap.into_sorted_vec(), vec![1, 2, 3]);
+  /// ```
+  pub fn into_sorted_vec(self) -> Vec<T> {
+    let mut sorted: Vec<T> = self.left.into_vec_asc().into_iter().map(|entry| entry.value).collect();
+    sorted.extend(self.right.into_vec_desc().into_iter().rev().map(|entry| entry.value));
+    sorted
+  }
+}
+
+impl<T: Ord + Clone + 'static> Extend<T> for MedianHeap<T> {
+  /// Extends the heap with the contents of an iterator, respecting `max_size` for each pushed
+  /// item exactly as a single [`MedianHeap::push`] call would.
+  fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+    for item in iter {
+      self.push(item);
+    }
+  }
+}
+
+impl<T: Ord + Clone + 'static> FromIterator<T> for MedianHeap<T> {
+  /// Builds an unbounded `MedianHeap` by pushing every item of the iterator in turn.
+  fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+    let mut heap = Self::new();
+    heap.extend(iter);
+    heap
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, K: Ord> serde::Serialize for MedianHeap<T, K> {
+  /// Serializes a `MedianHeap` as its logical multiset of elements plus `max_size`, not the
+  /// raw left/right heap split, since that split is an implementation detail that is rebuilt
+  /// on deserialization.
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+
+    let mut state = serializer.serialize_struct("MedianHeap", 2)?;
+    state.serialize_field("max_size", &self.max_size)?;
+    state.serialize_field("items", &self.values().collect::<Vec<_>>())?;
+    state.end()
+  }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Ord + Clone + 'static> serde::Deserialize<'de> for MedianHeap<T> {
+  /// Deserializes a `MedianHeap` by replaying its serialized elements through [`MedianHeap::push`]
+  /// (after restoring `max_size`), so the left/right balance invariant is reconstructed correctly
+  /// regardless of how the elements were ordered when serialized.
+  ///
+  /// Heaps built with [`MedianHeap::new_by_key`] cannot round-trip their key function and are
+  /// not `Deserialize`; only the identity-keyed `MedianHeap<T>` is supported.
+  fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(bound = "T: serde::Deserialize<'de>")]
+    struct Data<T> {
+      max_size: Option<usize>,
+      items: Vec<T>,
+    }
+
+    let data = Data::<T>::deserialize(deserializer)?;
+
+    let mut heap = match data.max_size {
+      Some(max_size) => MedianHeap::with_max_size(max_size),
+      None => MedianHeap::new(),
+    };
+
+    for item in data.items {
+      heap.push(item);
+    }
+
+    Ok(heap)
+  }
 }
 
 #[cfg(all(test, feature = "ordered-float"))]
@@ -411,10 +1015,10 @@ mod tests {
     let mut heap = MedianHeap::<i32>::with_max_size(8);
 
     for i in 0..100 {
-      heap.push((i as f32).try_into().unwrap());
+      heap.push(i);
 
       if i < 8 {
-        assert_eq!(heap.len(), i + 1);
+        assert_eq!(heap.len(), (i + 1) as usize);
       } else {
         assert_eq!(heap.len(), 8);
       }
@@ -434,6 +1038,25 @@ mod tests {
     MedianHeap::<i32>::new();
   }
 
+  #[test]
+  fn by_key() {
+    let mut heap = MedianHeap::new_by_key(|s: &String| s.len());
+
+    heap.push(String::from("a"));
+    heap.push(String::from("abc"));
+    heap.push(String::from("ab"));
+
+    assert_eq!(heap.median(), Some(Median::Single(&String::from("ab"))));
+  }
+
+  fn values_asc(heap: &MedianHeap<i32>) -> Vec<i32> {
+    heap.left.clone().into_vec_asc().into_iter().map(|entry| entry.value).collect()
+  }
+
+  fn values_desc(heap: &MedianHeap<i32>) -> Vec<i32> {
+    heap.right.clone().into_vec_desc().into_iter().map(|entry| entry.value).collect()
+  }
+
   #[test]
   fn max_size_balancing() {
     let mut heap = MedianHeap::<i32>::with_max_size(8);
@@ -442,31 +1065,192 @@ mod tests {
       heap.push(100);
     }
 
-    assert_eq!(heap.left.clone().into_vec_asc(), vec![100; 4]);
-    assert_eq!(heap.right.clone().into_vec_desc(), vec![100; 4]);
+    assert_eq!(values_asc(&heap), vec![100; 4]);
+    assert_eq!(values_desc(&heap), vec![100; 4]);
 
     for _ in 0..(8 * 3 / 2) {
       heap.push(2);
-      dbg!(&heap);
     }
 
-    assert_eq!(heap.left.clone().into_vec_asc(), vec![2; 4]);
-    assert_eq!(heap.right.clone().into_vec_desc(), vec![2; 4]);
+    assert_eq!(values_asc(&heap), vec![2; 4]);
+    assert_eq!(values_desc(&heap), vec![2; 4]);
+
+    heap.push(1);
+    assert_eq!(values_asc(&heap), vec![1, 2, 2, 2]);
+    assert_eq!(values_desc(&heap), vec![2, 2, 2, 2]);
+
+    heap.push(1);
+    assert_eq!(values_asc(&heap), vec![1, 1, 2, 2]);
+    assert_eq!(values_desc(&heap), vec![2, 2, 2, 2]);
+
+    heap.push(3);
+    assert_eq!(values_asc(&heap), vec![1, 2, 2, 2]);
+    assert_eq!(values_desc(&heap), vec![3, 2, 2, 2]);
+
+    heap.push(2);
+    assert_eq!(values_asc(&heap), vec![2; 4]);
+    assert_eq!(values_desc(&heap), vec![2; 3]);
+  }
+
+  #[test]
+  fn iter() {
+    let mut heap = MedianHeap::<i32>::new();
+
+    heap.push(1);
+    heap.push(2);
+    heap.push(3);
+
+    let mut values: Vec<_> = heap.iter().copied().collect();
+    values.sort();
+
+    assert_eq!(values, vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn into_sorted_vec() {
+    let mut heap = MedianHeap::<i32>::new();
+
+    heap.push(3);
+    heap.push(1);
+    heap.push(4);
+    heap.push(1);
+    heap.push(5);
+
+    assert_eq!(heap.into_sorted_vec(), vec![1, 1, 3, 4, 5]);
+  }
+
+  #[test]
+  fn from_iterator() {
+    let heap: MedianHeap<i32> = vec![3, 1, 4, 1, 5].into_iter().collect();
+
+    assert_eq!(heap.len(), 5);
+    assert_eq!(heap.into_sorted_vec(), vec![1, 1, 3, 4, 5]);
+  }
 
+  #[test]
+  fn extend() {
+    let mut heap = MedianHeap::<i32>::with_max_size(3);
+
+    heap.push(1);
+    heap.extend(vec![2, 3, 4]);
+
+    assert_eq!(heap.len(), 3);
+  }
+
+  #[test]
+  fn pop_min() {
+    let mut heap = MedianHeap::<i32>::new();
+
+    heap.push(2);
     heap.push(1);
-    assert_eq!(heap.left.clone().into_vec_asc(), vec![1, 2, 2, 2],);
-    assert_eq!(heap.right.clone().into_vec_desc(), vec![2, 2, 2, 2],);
+    heap.push(3);
+
+    assert_eq!(heap.pop_min(), Some(1));
+    assert_eq!(heap.pop_min(), Some(2));
+    assert_eq!(heap.pop_min(), Some(3));
+    assert_eq!(heap.pop_min(), None);
+  }
+
+  #[test]
+  fn pop_max() {
+    let mut heap = MedianHeap::<i32>::new();
 
+    heap.push(2);
     heap.push(1);
-    assert_eq!(heap.left.clone().into_vec_asc(), vec![1, 1, 2, 2],);
-    assert_eq!(heap.right.clone().into_vec_desc(), vec![2, 2, 2, 2],);
+    heap.push(3);
 
+    assert_eq!(heap.pop_max(), Some(3));
+    assert_eq!(heap.pop_max(), Some(2));
+    assert_eq!(heap.pop_max(), Some(1));
+    assert_eq!(heap.pop_max(), None);
+  }
+
+  #[test]
+  fn pop_median() {
+    let mut heap = MedianHeap::<i32>::new();
+
+    heap.push(1);
+    heap.push(2);
     heap.push(3);
-    assert_eq!(heap.left.clone().into_vec_asc(), vec![1, 2, 2, 2],);
-    assert_eq!(heap.right.clone().into_vec_desc(), vec![3, 2, 2, 2],);
+    heap.push(4);
+
+    assert_eq!(heap.pop_median(), Some(Median::Pair(2, 3)));
+    assert_eq!(heap.pop_median(), Some(Median::Pair(1, 4)));
+    assert_eq!(heap.pop_median(), None);
+  }
+
+  #[test]
+  fn pop_min_max_interleaved() {
+    let mut heap: MedianHeap<i32> = (1..=10).collect();
+
+    assert_eq!(heap.pop_min(), Some(1));
+    assert_eq!(heap.pop_max(), Some(10));
+    assert_eq!(heap.pop_min(), Some(2));
+    assert_eq!(heap.pop_max(), Some(9));
 
+    assert_eq!(heap.into_sorted_vec(), vec![3, 4, 5, 6, 7, 8]);
+  }
+
+  #[test]
+  fn clear() {
+    let mut heap = MedianHeap::<i32>::new();
+
+    heap.push(1);
     heap.push(2);
-    assert_eq!(heap.left.clone().into_vec_asc(), vec![2; 4]);
-    assert_eq!(heap.right.clone().into_vec_desc(), vec![2; 3]);
+    heap.clear();
+
+    assert!(heap.is_empty());
+    assert_eq!(heap.median(), None);
+  }
+
+  #[test]
+  fn mean_sample_variance_error() {
+    let mut heap = MedianHeap::<i32>::new();
+
+    assert_eq!(heap.mean(), None);
+    assert_eq!(heap.sample_variance(), None);
+    assert_eq!(heap.error(), None);
+
+    for value in [2, 4, 4, 4, 5, 5, 7, 9] {
+      heap.push(value);
+    }
+
+    assert_eq!(heap.mean(), Some(5.0));
+    assert_eq!(heap.sample_variance(), Some(4.571428571428571));
+    assert_eq!(heap.error(), Some(0.7559289460184544));
+  }
+
+  #[test]
+  fn append() {
+    let mut a = MedianHeap::<i32>::new();
+    a.push(1);
+    a.push(3);
+    a.push(5);
+
+    let mut b = MedianHeap::<i32>::new();
+    b.push(2);
+    b.push(4);
+
+    a.append(&mut b);
+
+    assert!(b.is_empty());
+    assert_eq!(a.into_sorted_vec(), vec![1, 2, 3, 4, 5]);
+  }
+
+  #[cfg(feature = "serde")]
+  #[test]
+  fn serde_round_trip() {
+    let mut heap = MedianHeap::<i32>::with_max_size(3);
+
+    heap.push(3);
+    heap.push(1);
+    heap.push(4);
+    heap.push(1);
+
+    let json = serde_json::to_string(&heap).unwrap();
+    let round_tripped: MedianHeap<i32> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(round_tripped.max_size(), heap.max_size());
+    assert_eq!(round_tripped.into_sorted_vec(), heap.into_sorted_vec());
   }
 }
@@ -1,8 +1,13 @@
-use std::cmp::Ordering;
-use std::fmt::{Debug, Formatter, Result};
+use core::cmp::Ordering;
+use core::fmt::{Debug, Formatter, Result};
 
+/// A value ordered by the reverse of its natural `Ord` implementation.
+///
+/// Wrapping a value in `Min` before storing it in a max-heap makes that heap behave like a
+/// min-heap over the wrapped type, so [`crate::windowed_median_heap`] can reuse one max-heap
+/// implementation for both the ascending and descending halves of its sliding window.
 #[derive(PartialEq, Eq)]
-pub struct Min<T: Ord>(pub T);
+pub(crate) struct Min<T: Ord>(pub(crate) T);
 
 impl<T: Ord> PartialOrd for Min<T> {
   fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
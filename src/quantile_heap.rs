@@ -0,0 +1,290 @@
+use core::fmt::{Debug, Formatter, Result};
+
+use min_max_heap::MinMaxHeap;
+
+use crate::AverageWith;
+
+/// A heap that tracks an arbitrary running φ-quantile, generalizing the two-heap median
+/// technique [`crate::MedianHeap`] uses for φ = 0.5 to any φ ∈ (0, 1).
+///
+/// `left` holds the lowest φ-fraction of elements and `right` the remaining `1 - φ`-fraction,
+/// rebalanced after every push so that `left.len()` tracks `round(φ * n)`.
+pub struct QuantileHeap<T: Ord> {
+  phi: f64,
+  max_size: Option<usize>,
+  left: MinMaxHeap<T>,
+  right: MinMaxHeap<T>,
+}
+
+impl<T: Ord + Debug> Debug for QuantileHeap<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    let mut s = f.debug_struct("QuantileHeap");
+
+    s.field("phi", &self.phi);
+
+    if let Some(max_size) = self.max_size {
+      s.field("max_size", &max_size);
+    }
+
+    s.field("left", &self.left);
+    s.field("right", &self.right);
+
+    s.finish()
+  }
+}
+
+impl<T: Ord> QuantileHeap<T> {
+  /// Creates an empty `QuantileHeap` tracking the `phi`-quantile.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `phi` is not in the open interval `(0, 1)`. Use [`crate::MedianHeap`] for the
+  /// φ = 0.5 case.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::QuantileHeap;
+  /// #
+  /// let mut heap = QuantileHeap::new(0.9);
+  /// heap.push(4);
+  /// ```
+  #[inline]
+  pub fn new(phi: f64) -> Self {
+    assert!(phi > 0.0 && phi < 1.0, "phi must be in (0, 1)");
+
+    Self { phi, max_size: None, left: Default::default(), right: Default::default() }
+  }
+
+  /// Creates an empty `QuantileHeap` tracking the `phi`-quantile, which can only grow to
+  /// `max_size`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `phi` is not in the open interval `(0, 1)`, or if `max_size` is zero.
+  #[inline]
+  pub fn with_max_size(phi: f64, max_size: usize) -> Self {
+    assert!(phi > 0.0 && phi < 1.0, "phi must be in (0, 1)");
+    assert!(max_size > 0);
+
+    let heap_size = (max_size + 3) / 2;
+
+    Self {
+      phi,
+      max_size: Some(max_size),
+      left: MinMaxHeap::with_capacity(heap_size),
+      right: MinMaxHeap::with_capacity(heap_size),
+    }
+  }
+
+  /// Returns the tracked quantile, φ.
+  pub fn phi(&self) -> f64 {
+    self.phi
+  }
+
+  /// Returns the maximum size the heap can grow to.
+  pub fn max_size(&self) -> Option<usize> {
+    self.max_size
+  }
+
+  /// Returns the length of the heap.
+  pub fn len(&self) -> usize {
+    self.left.len() + self.right.len()
+  }
+
+  /// Returns `true` if there are no elements on the heap.
+  pub fn is_empty(&self) -> bool {
+    self.left.is_empty() && self.right.is_empty()
+  }
+
+  fn is_full(&self) -> bool {
+    if let Some(max_size) = self.max_size {
+      self.len() >= max_size
+    } else {
+      false
+    }
+  }
+
+  /// Returns `round(phi * n)`, clamped to `[0, n]`.
+  fn target_left_len(&self, n: usize) -> usize {
+    ((self.phi * n as f64).round() as usize).min(n)
+  }
+
+  /// Pushes an item onto the heap.
+  ///
+  /// Once `max_size` is reached, this evicts the smallest item if the pushed item falls above
+  /// the current quantile boundary, or the largest item if it falls below it, mirroring how
+  /// [`crate::MedianHeap::push`] evicts around the median.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::QuantileHeap;
+  /// #
+  /// let mut heap = QuantileHeap::new(0.9);
+  ///
+  /// heap.push(1);
+  /// heap.push(2);
+  /// heap.push(3);
+  ///
+  /// assert_eq!(heap.len(), 3);
+  /// ```
+  pub fn push(&mut self, item: T) {
+    let goes_left = match (self.left.peek_max(), self.right.peek_min()) {
+      (Some(l), Some(_)) if item <= *l => true,
+      (Some(_), Some(r)) if item >= *r => false,
+      (Some(_), Some(_)) => self.left.len() < self.target_left_len(self.len() + 1),
+      (Some(l), None) => item <= *l,
+      (None, Some(r)) => item < *r,
+      (None, None) => true,
+    };
+
+    // When the side to evict from is empty, its extreme lives on the other side instead.
+    if self.is_full() {
+      if goes_left {
+        self.right.pop_max().or_else(|| self.left.pop_max());
+      } else {
+        self.left.pop_min().or_else(|| self.right.pop_min());
+      }
+    }
+
+    if goes_left {
+      self.left.push(item);
+    } else {
+      self.right.push(item);
+    }
+
+    self.rebalance();
+  }
+
+  /// Moves elements across the left/right split until `left.len()` matches `target_left_len`.
+  fn rebalance(&mut self) {
+    let target = self.target_left_len(self.len());
+
+    while self.left.len() > target {
+      self.right.push(self.left.pop_max().unwrap());
+    }
+
+    while self.left.len() < target {
+      self.left.push(self.right.pop_min().unwrap());
+    }
+  }
+}
+
+impl<T: Ord + AverageWith + Clone> QuantileHeap<T> {
+  /// Returns the φ-quantile estimate, or `None` if the heap is empty.
+  ///
+  /// If `phi * len()` is exactly an integer, the quantile falls exactly on the boundary
+  /// between the two halves and this returns the arithmetic mean of the two boundary
+  /// elements (the same interpolation [`crate::MedianHeap::median`] would need for φ = 0.5
+  /// with an even number of elements). Otherwise it returns the single boundary element of
+  /// whichever side holds the target rank.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::QuantileHeap;
+  /// #
+  /// let mut heap = QuantileHeap::new(0.5);
+  ///
+  /// heap.push(1);
+  /// assert_eq!(heap.quantile(), Some(1));
+  ///
+  /// heap.push(3);
+  /// assert_eq!(heap.quantile(), Some(2));
+  /// ```
+  pub fn quantile(&self) -> Option<T> {
+    if self.is_empty() {
+      return None;
+    }
+
+    let rank = self.phi * self.len() as f64;
+
+    if rank.fract() == 0.0 {
+      match (self.left.peek_max(), self.right.peek_min()) {
+        (Some(left), Some(right)) => Some(left.average_with(right)),
+        (Some(left), None) => Some(left.clone()),
+        (None, Some(right)) => Some(right.clone()),
+        (None, None) => None,
+      }
+    } else {
+      // `left.len()` tracks `target_left_len(n)` (see `rebalance`), so the target rank sits in
+      // `left` whenever it's non-empty; `left` is only empty when the target rank is 0, i.e.
+      // phi is small enough that the boundary falls before the first element. Picking a side
+      // based on `left.len().cmp(&right.len())` instead would pick the wrong side whenever
+      // `right` happens to be longer despite still holding an element past the target rank.
+      match self.left.peek_max() {
+        Some(left) => Some(left.clone()),
+        None => self.right.peek_min().cloned(),
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn matches_median_at_0_5() {
+    let mut heap = QuantileHeap::<i32>::new(0.5);
+
+    heap.push(1);
+    assert_eq!(heap.quantile(), Some(1));
+
+    heap.push(2);
+    assert_eq!(heap.quantile(), Some(1));
+
+    heap.push(3);
+    assert_eq!(heap.quantile(), Some(2));
+
+    heap.push(4);
+    assert_eq!(heap.quantile(), Some(2));
+  }
+
+  #[test]
+  fn high_phi() {
+    let mut heap = QuantileHeap::<i32>::new(0.9);
+
+    for i in 1..=10 {
+      heap.push(i);
+    }
+
+    assert_eq!(heap.quantile(), Some(9));
+  }
+
+  #[test]
+  #[should_panic]
+  fn phi_out_of_range() {
+    QuantileHeap::<i32>::new(1.0);
+  }
+
+  #[test]
+  fn low_phi_non_boundary() {
+    let mut heap = QuantileHeap::<i32>::new(0.1);
+
+    for i in [144, 967, 682, 793, 748] {
+      heap.push(i);
+    }
+
+    assert_eq!(heap.quantile(), Some(144));
+  }
+
+  #[test]
+  fn with_max_size() {
+    let mut heap = QuantileHeap::<i32>::with_max_size(0.9, 10);
+
+    for i in 1..=100 {
+      heap.push(i);
+    }
+
+    assert_eq!(heap.len(), 10);
+    assert_eq!(heap.quantile(), Some(99));
+  }
+}
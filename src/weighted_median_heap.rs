@@ -0,0 +1,280 @@
+use core::cmp::Ordering;
+use core::fmt::{Debug, Formatter, Result};
+
+use min_max_heap::MinMaxHeap;
+
+use crate::AverageWith;
+
+/// An entry paired with the weight it was pushed with.
+///
+/// Only `value` takes part in comparisons, so entries can be kept in a [`MinMaxHeap`] ordered by
+/// `T` alone while still carrying their weight along for rebalancing.
+struct Entry<T> {
+  value: T,
+  weight: f64,
+}
+
+impl<T: PartialEq> PartialEq for Entry<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.value == other.value
+  }
+}
+
+impl<T: Eq> Eq for Entry<T> {}
+
+impl<T: PartialOrd> PartialOrd for Entry<T> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    self.value.partial_cmp(&other.value)
+  }
+}
+
+impl<T: Ord> Ord for Entry<T> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.value.cmp(&other.value)
+  }
+}
+
+impl<T: Debug> Debug for Entry<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    Debug::fmt(&self.value, f)
+  }
+}
+
+/// A [`crate::MedianHeap`] variant where each pushed element carries a non-negative weight, so
+/// [`WeightedMedianHeap::median`] returns the weighted median: the smallest value whose
+/// cumulative weight, summed over every element `<=` it, reaches at least half of the total
+/// weight pushed so far.
+///
+/// `low` holds the lower half of the distribution, `high` holds the upper half, and
+/// `low_weight`/`high_weight` track the summed weight on each side, generalizing the plain
+/// element-count split [`crate::MedianHeap`] uses; pushing every element with weight `1.0`
+/// reduces to the unweighted case.
+pub struct WeightedMedianHeap<T: Ord> {
+  low: MinMaxHeap<Entry<T>>,
+  high: MinMaxHeap<Entry<T>>,
+  low_weight: f64,
+  high_weight: f64,
+}
+
+impl<T: Ord> Default for WeightedMedianHeap<T> {
+  /// Creates an empty `WeightedMedianHeap`.
+  #[inline]
+  fn default() -> Self {
+    Self { low: Default::default(), high: Default::default(), low_weight: 0.0, high_weight: 0.0 }
+  }
+}
+
+impl<T: Ord + Debug> Debug for WeightedMedianHeap<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.debug_struct("WeightedMedianHeap")
+      .field("low_weight", &self.low_weight)
+      .field("high_weight", &self.high_weight)
+      .field("low", &self.low)
+      .field("high", &self.high)
+      .finish()
+  }
+}
+
+impl<T: Ord> WeightedMedianHeap<T> {
+  /// Creates an empty `WeightedMedianHeap`.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::WeightedMedianHeap;
+  /// #
+  /// let mut heap = WeightedMedianHeap::new();
+  /// heap.push(4, 1.0);
+  /// ```
+  #[inline]
+  pub fn new() -> Self {
+    Default::default()
+  }
+
+  /// Returns the total weight pushed onto the heap.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::WeightedMedianHeap;
+  /// #
+  /// let mut heap = WeightedMedianHeap::new();
+  ///
+  /// heap.push(1, 2.0);
+  /// heap.push(2, 0.5);
+  ///
+  /// assert_eq!(heap.total_weight(), 2.5);
+  /// ```
+  pub fn total_weight(&self) -> f64 {
+    self.low_weight + self.high_weight
+  }
+
+  /// Returns the number of elements pushed onto the heap.
+  pub fn len(&self) -> usize {
+    self.low.len() + self.high.len()
+  }
+
+  /// Returns `true` if there are no elements on the heap.
+  pub fn is_empty(&self) -> bool {
+    self.low.is_empty() && self.high.is_empty()
+  }
+
+  /// Pushes `item` onto the heap with the given non-negative `weight`.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `weight` is negative.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::WeightedMedianHeap;
+  /// #
+  /// let mut heap = WeightedMedianHeap::new();
+  ///
+  /// heap.push(1, 1.0);
+  /// heap.push(2, 1.0);
+  /// heap.push(3, 1.0);
+  ///
+  /// assert_eq!(heap.len(), 3);
+  /// ```
+  pub fn push(&mut self, item: T, weight: f64) {
+    assert!(weight >= 0.0, "weight must not be negative");
+
+    let goes_low = match (self.low.peek_max(), self.high.peek_min()) {
+      (Some(l), _) if item <= l.value => true,
+      (_, Some(h)) if item >= h.value => false,
+      (Some(_), Some(_)) => self.low_weight <= self.high_weight,
+      (Some(_), None) => true,
+      (None, Some(_)) => false,
+      (None, None) => true,
+    };
+
+    let entry = Entry { value: item, weight };
+
+    if goes_low {
+      self.low.push(entry);
+      self.low_weight += weight;
+    } else {
+      self.high.push(entry);
+      self.high_weight += weight;
+    }
+
+    self.rebalance(weight);
+  }
+
+  /// Moves entries from `low` to `high` while `low` outweighs `high` by more than half of
+  /// `tolerance` (the weight of the element that was just pushed), then from `high` to `low`
+  /// while `high` outweighs `low` by more than that. Afterwards `high` outweighs `low` by at most
+  /// `tolerance / 2`, but `low` can outweigh `high` by more, since the second pass may move a
+  /// heavy entry across without the first pass running again.
+  fn rebalance(&mut self, tolerance: f64) {
+    while self.low_weight - self.high_weight > tolerance / 2.0 {
+      let entry = self.low.pop_max().unwrap();
+      self.low_weight -= entry.weight;
+      self.high_weight += entry.weight;
+      self.high.push(entry);
+    }
+
+    while self.high_weight - self.low_weight > tolerance / 2.0 {
+      let entry = self.high.pop_min().unwrap();
+      self.high_weight -= entry.weight;
+      self.low_weight += entry.weight;
+      self.low.push(entry);
+    }
+  }
+}
+
+impl<T: Ord + AverageWith + Clone> WeightedMedianHeap<T> {
+  /// Returns the weighted median, or `None` if the heap is empty.
+  ///
+  /// If the total weight splits exactly in half between the two sides, this returns the
+  /// arithmetic mean of the two boundary values via [`AverageWith`]; otherwise it returns the
+  /// boundary value of whichever side carries more weight.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::WeightedMedianHeap;
+  /// #
+  /// let mut heap = WeightedMedianHeap::new();
+  ///
+  /// heap.push(1, 1.0);
+  /// assert_eq!(heap.median(), Some(1));
+  ///
+  /// heap.push(3, 1.0);
+  /// assert_eq!(heap.median(), Some(2));
+  ///
+  /// heap.push(10, 10.0);
+  /// assert_eq!(heap.median(), Some(10));
+  /// ```
+  pub fn median(&self) -> Option<T> {
+    match (self.low.peek_max(), self.high.peek_min()) {
+      (Some(low), Some(high)) if self.low_weight == self.high_weight => Some(low.value.average_with(&high.value)),
+      (Some(low), _) if self.low_weight >= self.high_weight => Some(low.value.clone()),
+      (_, Some(high)) => Some(high.value.clone()),
+      (Some(low), None) => Some(low.value.clone()),
+      (None, None) => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn push_unweighted() {
+    let mut heap = WeightedMedianHeap::<i32>::new();
+
+    heap.push(1, 1.0);
+    assert_eq!(heap.median(), Some(1));
+
+    heap.push(2, 1.0);
+    assert_eq!(heap.median(), Some(1));
+
+    heap.push(3, 1.0);
+    assert_eq!(heap.median(), Some(2));
+
+    heap.push(4, 1.0);
+    assert_eq!(heap.median(), Some(2));
+  }
+
+  #[test]
+  fn push_weighted() {
+    let mut heap = WeightedMedianHeap::<i32>::new();
+
+    heap.push(1, 1.0);
+    heap.push(2, 1.0);
+    heap.push(10, 10.0);
+
+    assert_eq!(heap.total_weight(), 12.0);
+    assert_eq!(heap.median(), Some(10));
+  }
+
+  #[test]
+  fn even_split() {
+    let mut heap = WeightedMedianHeap::<i32>::new();
+
+    heap.push(1, 2.0);
+    heap.push(3, 2.0);
+
+    assert_eq!(heap.median(), Some(2));
+  }
+
+  #[test]
+  fn empty() {
+    let heap = WeightedMedianHeap::<i32>::new();
+
+    assert!(heap.is_empty());
+    assert_eq!(heap.median(), None);
+  }
+}
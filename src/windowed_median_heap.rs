@@ -0,0 +1,428 @@
+use std::cmp::Ordering::{self, *};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{Debug, Formatter, Result};
+
+use crate::min::Min;
+use crate::Median;
+
+/// An entry tagged with the sequence number it was pushed with, so a specific entry can later
+/// be identified and removed once it ages out of a [`WindowedMedianHeap`]'s window.
+///
+/// Only `value` takes part in comparisons.
+struct Entry<T> {
+  seq: u64,
+  value: T,
+}
+
+impl<T: PartialEq> PartialEq for Entry<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.value == other.value
+  }
+}
+
+impl<T: Eq> Eq for Entry<T> {}
+
+impl<T: PartialOrd> PartialOrd for Entry<T> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    self.value.partial_cmp(&other.value)
+  }
+}
+
+impl<T: Ord> Ord for Entry<T> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    self.value.cmp(&other.value)
+  }
+}
+
+impl<T: Debug> Debug for Entry<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    Debug::fmt(&self.value, f)
+  }
+}
+
+/// An array-backed binary max-heap of [`Entry`] values that also supports removing an arbitrary,
+/// non-extreme entry in `O(log n)` given its sequence number.
+///
+/// [`min_max_heap::MinMaxHeap`], used by the other backends in this crate, only ever exposes its
+/// extremes, so it cannot support removing an aged-out entry that isn't currently at a boundary.
+/// `IndexedHeap` keeps a `seq -> index` position map alongside the heap array and keeps it in
+/// sync on every swap, so a tracked entry can be found and removed directly instead of only
+/// being reachable once it bubbles up to the top.
+struct IndexedHeap<O> {
+  items: Vec<Entry<O>>,
+  pos: HashMap<u64, usize>,
+}
+
+impl<O> Default for IndexedHeap<O> {
+  fn default() -> Self {
+    Self { items: Vec::new(), pos: HashMap::new() }
+  }
+}
+
+impl<O: Debug> Debug for IndexedHeap<O> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.debug_list().entries(self.items.iter()).finish()
+  }
+}
+
+impl<O: Ord> IndexedHeap<O> {
+  fn with_capacity(capacity: usize) -> Self {
+    Self { items: Vec::with_capacity(capacity), pos: HashMap::with_capacity(capacity) }
+  }
+
+  fn len(&self) -> usize {
+    self.items.len()
+  }
+
+  fn peek_max(&self) -> Option<&Entry<O>> {
+    self.items.first()
+  }
+
+  fn swap(&mut self, a: usize, b: usize) {
+    self.items.swap(a, b);
+    self.pos.insert(self.items[a].seq, a);
+    self.pos.insert(self.items[b].seq, b);
+  }
+
+  fn sift_up(&mut self, mut i: usize) {
+    while i > 0 {
+      let parent = (i - 1) / 2;
+
+      if self.items[parent] < self.items[i] {
+        self.swap(parent, i);
+        i = parent;
+      } else {
+        break;
+      }
+    }
+  }
+
+  fn sift_down(&mut self, mut i: usize) {
+    loop {
+      let (left, right) = (2 * i + 1, 2 * i + 2);
+      let mut largest = i;
+
+      if left < self.items.len() && self.items[left] > self.items[largest] {
+        largest = left;
+      }
+
+      if right < self.items.len() && self.items[right] > self.items[largest] {
+        largest = right;
+      }
+
+      if largest == i {
+        break;
+      }
+
+      self.swap(largest, i);
+      i = largest;
+    }
+  }
+
+  fn push(&mut self, entry: Entry<O>) {
+    let i = self.items.len();
+    self.pos.insert(entry.seq, i);
+    self.items.push(entry);
+    self.sift_up(i);
+  }
+
+  fn pop_max(&mut self) -> Option<Entry<O>> {
+    let last = self.items.len().checked_sub(1)?;
+    self.swap(0, last);
+
+    let entry = self.items.pop().unwrap();
+    self.pos.remove(&entry.seq);
+
+    if !self.items.is_empty() {
+      self.sift_down(0);
+    }
+
+    Some(entry)
+  }
+
+  /// Removes and returns the entry tagged with `seq`, wherever it currently sits in the heap.
+  fn remove(&mut self, seq: u64) -> Option<Entry<O>> {
+    let i = self.pos.remove(&seq)?;
+    let last = self.items.len() - 1;
+
+    if i != last {
+      self.swap(i, last);
+    }
+
+    let entry = self.items.pop().unwrap();
+    self.pos.remove(&entry.seq);
+
+    if i < self.items.len() {
+      self.sift_up(i);
+      self.sift_down(i);
+    }
+
+    Some(entry)
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Side {
+  Left,
+  Right,
+}
+
+/// A moving-median filter over the last `window` pushed elements.
+///
+/// Unlike [`crate::MedianHeap::with_max_size`], which bounds the heap by evicting the smallest
+/// or largest element, `WindowedMedianHeap` evicts the *oldest* element once the window is full,
+/// making it suitable as a streaming median filter for sensor or signal data.
+///
+/// Entries are tagged with a sequence number recording insertion order; a ring buffer of those
+/// sequence numbers keeps them oldest first, and a `seq -> Side` map tracks which side of the
+/// split each live entry currently sits on, so the oldest entry can be found in `O(1)` and then
+/// removed directly from whichever side's
+/// [`IndexedHeap`] it sits on, in `O(log n)`, no matter where in that heap it currently is.
+pub struct WindowedMedianHeap<T: Ord> {
+  window: usize,
+  next_seq: u64,
+  order: VecDeque<u64>,
+  side_of: HashMap<u64, Side>,
+  left: IndexedHeap<T>,
+  right: IndexedHeap<Min<T>>,
+}
+
+impl<T: Ord + Debug> Debug for WindowedMedianHeap<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+    f.debug_struct("WindowedMedianHeap")
+      .field("window", &self.window)
+      .field("left", &self.left)
+      .field("right", &self.right)
+      .finish()
+  }
+}
+
+impl<T: Ord> WindowedMedianHeap<T> {
+  /// Creates an empty `WindowedMedianHeap` that keeps a moving median over the last `window`
+  /// pushed elements.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `window` is zero.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::WindowedMedianHeap;
+  /// #
+  /// let mut heap = WindowedMedianHeap::with_window(3);
+  /// heap.push(4);
+  /// ```
+  #[inline]
+  pub fn with_window(window: usize) -> Self {
+    assert!(window > 0, "window must be greater than 0");
+
+    Self {
+      window,
+      next_seq: 0,
+      order: VecDeque::with_capacity(window),
+      side_of: HashMap::with_capacity(window),
+      left: IndexedHeap::with_capacity(window / 2 + 1),
+      right: IndexedHeap::with_capacity(window / 2 + 1),
+    }
+  }
+
+  /// Returns the size of the window.
+  pub fn window(&self) -> usize {
+    self.window
+  }
+
+  /// Returns the number of elements currently in the window.
+  pub fn len(&self) -> usize {
+    self.left.len() + self.right.len()
+  }
+
+  /// Returns `true` if there are no elements in the window.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  fn is_full(&self) -> bool {
+    self.len() >= self.window
+  }
+
+  /// Evicts the oldest element still in the window, if any.
+  fn evict_oldest(&mut self) {
+    if let Some(seq) = self.order.pop_front() {
+      match self.side_of.remove(&seq) {
+        Some(Side::Left) => {
+          self.left.remove(seq);
+        },
+        Some(Side::Right) => {
+          self.right.remove(seq);
+        },
+        None => {},
+      }
+    }
+  }
+
+  /// Moves boundary entries across the split until the two sides differ in length by at most
+  /// one.
+  fn rebalance(&mut self) {
+    while self.left.len() > self.right.len() + 1 {
+      let entry = self.left.pop_max().unwrap();
+      self.side_of.insert(entry.seq, Side::Right);
+      self.right.push(Entry { seq: entry.seq, value: Min(entry.value) });
+    }
+
+    while self.right.len() > self.left.len() {
+      let entry = self.right.pop_max().unwrap();
+      self.side_of.insert(entry.seq, Side::Left);
+      self.left.push(Entry { seq: entry.seq, value: entry.value.0 });
+    }
+  }
+
+  /// Pushes an item onto the window, evicting the oldest item once the window is full.
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::WindowedMedianHeap;
+  /// #
+  /// let mut heap = WindowedMedianHeap::with_window(2);
+  ///
+  /// heap.push(1);
+  /// heap.push(2);
+  /// assert_eq!(heap.len(), 2);
+  ///
+  /// heap.push(3);
+  /// assert_eq!(heap.len(), 2);
+  /// ```
+  pub fn push(&mut self, value: T) {
+    if self.is_full() {
+      self.evict_oldest();
+    }
+
+    let goes_left = match (self.left.peek_max(), self.right.peek_max()) {
+      (Some(l), Some(_)) if value <= l.value => true,
+      (Some(_), Some(r)) if value >= r.value.0 => false,
+      (Some(_), Some(_)) => self.left.len() <= self.right.len(),
+      (Some(l), None) => value <= l.value,
+      (None, Some(r)) => value < r.value.0,
+      (None, None) => true,
+    };
+
+    let seq = self.next_seq;
+    self.next_seq += 1;
+    self.order.push_back(seq);
+
+    if goes_left {
+      self.side_of.insert(seq, Side::Left);
+      self.left.push(Entry { seq, value });
+    } else {
+      self.side_of.insert(seq, Side::Right);
+      self.right.push(Entry { seq, value: Min(value) });
+    }
+
+    self.rebalance();
+  }
+
+  /// Returns the running median over the current window.
+  ///
+  /// This either returns
+  ///   - `Some(T)` containing the median value if there are an odd number of elements
+  ///   - `Some(T)` containing the two middlemost values if there are an even number of elements
+  ///   - `None` if the window is empty
+  ///
+  /// # Examples
+  ///
+  /// Basic usage:
+  ///
+  /// ```
+  /// # use medianheap::{Median, WindowedMedianHeap};
+  /// #
+  /// let mut heap = WindowedMedianHeap::with_window(2);
+  ///
+  /// heap.push(1);
+  /// assert_eq!(heap.median(), Some(Median::Single(&1)));
+  ///
+  /// heap.push(3);
+  /// assert_eq!(heap.median(), Some(Median::Pair(&1, &3)));
+  /// ```
+  pub fn median(&self) -> Option<Median<&T>> {
+    match self.left.len().cmp(&self.right.len()) {
+      Less => self.right.peek_max().map(|entry| Median::Single(&entry.value.0)),
+      Greater => self.left.peek_max().map(|entry| Median::Single(&entry.value)),
+      Equal => self.left.peek_max().and_then(|left| self.right.peek_max().map(|right| Median::Pair(&left.value, &right.value.0))),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn push() {
+    let mut heap = WindowedMedianHeap::with_window(8);
+
+    heap.push(1);
+    assert_eq!(heap.median(), Some(Median::Single(&1)));
+
+    heap.push(2);
+    assert_eq!(heap.median(), Some(Median::Pair(&1, &2)));
+
+    heap.push(3);
+    assert_eq!(heap.median(), Some(Median::Single(&2)));
+  }
+
+  #[test]
+  fn evicts_oldest_not_extreme() {
+    let mut heap = WindowedMedianHeap::with_window(3);
+
+    heap.push(1);
+    heap.push(2);
+    heap.push(3);
+    assert_eq!(heap.median(), Some(Median::Single(&2)));
+
+    // Pushing a new minimum evicts the oldest element (`1`), not the new maximum or minimum.
+    heap.push(0);
+    assert_eq!(heap.len(), 3);
+    assert_eq!(heap.median(), Some(Median::Single(&2)));
+
+    heap.push(0);
+    assert_eq!(heap.len(), 3);
+    assert_eq!(heap.median(), Some(Median::Single(&0)));
+  }
+
+  #[test]
+  fn window_1() {
+    let mut heap = WindowedMedianHeap::with_window(1);
+
+    heap.push(1);
+    assert_eq!(heap.median(), Some(Median::Single(&1)));
+
+    heap.push(2);
+    assert_eq!(heap.len(), 1);
+    assert_eq!(heap.median(), Some(Median::Single(&2)));
+  }
+
+  #[test]
+  #[should_panic]
+  fn window_0() {
+    WindowedMedianHeap::<i32>::with_window(0);
+  }
+
+  #[test]
+  fn does_not_grow_unbounded() {
+    let mut heap = WindowedMedianHeap::with_window(100);
+
+    for i in 0..1_000_000 {
+      heap.push(i);
+    }
+
+    // Real indexed removal means the backing heaps never hold more than `window` entries
+    // between them, unlike lazy tombstoning, which only reclaims entries at a boundary.
+    assert_eq!(heap.len(), 100);
+    assert_eq!(heap.left.len() + heap.right.len(), 100);
+  }
+}